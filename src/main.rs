@@ -1,11 +1,19 @@
 use anyhow::{anyhow, bail, Result};
+use rayon::prelude::*;
 use std::{
     fs, io, os,
     path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
 };
 use structopt::StructOpt;
 use walkdir::WalkDir;
 
+#[cfg(windows)]
+use std::time::Duration;
+
 #[derive(Debug, StructOpt)]
 #[structopt(
     name = "obliterate",
@@ -13,72 +21,386 @@ use walkdir::WalkDir;
 )]
 struct Opt {
     paths: Vec<PathBuf>,
+
+    /// Number of threads to use for deleting sibling files/directories
+    /// concurrently. Defaults to the available parallelism. Pass `1` to
+    /// fall back to the original strictly serial walk.
+    #[structopt(long)]
+    jobs: Option<usize>,
+
+    /// Print a summary of the number of files/directories removed and the
+    /// total number of bytes freed.
+    #[structopt(long)]
+    stats: bool,
 }
 
 fn main() -> Result<()> {
     let opt = Opt::from_args();
 
+    let jobs = opt.jobs.unwrap_or_else(|| {
+        std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+    });
+
+    let removal = opt.stats.then(Removal::default);
+
     for path in opt.paths {
         // Errors are printed individually for each file, we do not care
         // if there was an error overall; it is only used for testing.
-        let _ = remove_path(&path);
+        let _ = remove_path(&path, jobs, removal.as_ref());
+    }
+
+    if let Some(removal) = &removal {
+        println!(
+            "Removed {} files ({}) and {} directories",
+            format_count(removal.num_files.load(Ordering::Relaxed)),
+            format_bytes(removal.total_bytes.load(Ordering::Relaxed)),
+            format_count(removal.num_dirs.load(Ordering::Relaxed)),
+        );
     }
 
     Ok(())
 }
 
 /// We have to use different functions to remove files or directories, so we
-/// use this to tell `remove_file_or_dir` which kind we are deleting.
+/// use this to tell `remove_file_or_dir` which kind we are deleting. Files
+/// and symlinks carry their own size (read from whatever metadata the
+/// caller already has to hand) so it can be added to the `--stats` totals
+/// without an extra stat.
+///
+/// Symlinks get their own variant rather than being folded into `File`:
+/// on Windows a directory symlink/junction must be removed with
+/// `fs::remove_dir`, not `fs::remove_file`, and on every platform the
+/// permission-recovery step must never follow a symlink to stat or chmod
+/// its target.
 enum FileOrDir {
-    File,
+    File { size: u64 },
     Dir,
+    Symlink { size: u64 },
+}
+
+/// Accumulates the number of files/directories removed and the total number
+/// of bytes freed, for the `--stats` flag. Shared across threads in
+/// parallel mode, so the counters are atomic.
+#[derive(Default)]
+struct Removal {
+    total_bytes: AtomicU64,
+    num_files: AtomicU64,
+    num_dirs: AtomicU64,
+}
+
+impl Removal {
+    fn record(&self, file_or_dir: &FileOrDir) {
+        match *file_or_dir {
+            FileOrDir::File { size } | FileOrDir::Symlink { size } => {
+                self.total_bytes.fetch_add(size, Ordering::Relaxed);
+                self.num_files.fetch_add(1, Ordering::Relaxed);
+            }
+            FileOrDir::Dir => {
+                self.num_dirs.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
 }
 
-/// Remove the entire directory tree (or file) at `path`.
-fn remove_path(path: &Path) -> Result<()> {
+/// Format a byte count as a human-readable size, e.g. `4.7 GiB`.
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 6] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[unit])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// Format an integer with thousands separators, e.g. `12,384`.
+fn format_count(n: u64) -> String {
+    let digits = n.to_string();
+    let mut result = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().rev().enumerate() {
+        if i > 0 && i % 3 == 0 {
+            result.push(',');
+        }
+        result.push(c);
+    }
+    result.chars().rev().collect()
+}
+
+/// Remove the entire directory tree (or file) at `path`, using `jobs`
+/// worker threads to delete sibling subtrees concurrently. `jobs == 1`
+/// uses the original strictly serial walk.
+fn remove_path(path: &Path, jobs: usize, removal: Option<&Removal>) -> Result<()> {
+    if jobs <= 1 {
+        return remove_path_serial(path, removal);
+    }
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs)
+        .build()
+        .map_err(|e| anyhow!("Failed to create thread pool: {}", e))?;
+
+    let failures = Mutex::new(Vec::new());
+    pool.install(|| remove_path_parallel(path, removal, &failures));
+
+    let failures = failures.into_inner().unwrap();
+    if !failures.is_empty() {
+        for failure in &failures {
+            eprintln!("{}", failure);
+        }
+        bail!("One or more errors deleting '{}'", path.display());
+    }
+    Ok(())
+}
+
+/// Remove the entire directory tree (or file) at `path`, strictly serially.
+fn remove_path_serial(path: &Path, removal: Option<&Removal>) -> Result<()> {
+    if remove_walk(WalkDir::new(path).contents_first(true), removal) {
+        Ok(())
+    } else {
+        bail!("One or more errors deleting '{}'", path.display());
+    }
+}
+
+/// Drive a `WalkDir` iterator to completion, deleting every entry it
+/// yields. Returns whether everything was removed successfully.
+///
+/// If a directory can't even be opened because it is missing its read or
+/// execute bit, `WalkDir` reports an access error for it instead of
+/// yielding its contents (this is the case for e.g. a `d---------`
+/// directory). When that happens we grant
+/// the directory read/write/execute and re-walk just that subtree rather
+/// than giving up on everything inside it.
+fn remove_walk(walker: WalkDir, removal: Option<&Removal>) -> bool {
     let mut success = true;
 
-    for entry in WalkDir::new(path).contents_first(true).into_iter() {
+    for entry in walker.into_iter() {
         match entry {
             Ok(entry) => {
-                if let Err(e) = remove_file_or_dir(
-                    entry.path(),
-                    if entry.file_type().is_dir() {
-                        FileOrDir::Dir
-                    } else {
-                        FileOrDir::File
-                    },
-                ) {
+                // Check `is_symlink()` first: a symlink is always a leaf to
+                // remove directly, never something to recurse into, even
+                // when it is a Windows directory symlink/junction.
+                let file_or_dir = if entry.file_type().is_symlink() {
+                    FileOrDir::Symlink {
+                        size: entry.metadata().map(|m| m.len()).unwrap_or(0),
+                    }
+                } else if entry.file_type().is_dir() {
+                    FileOrDir::Dir
+                } else {
+                    FileOrDir::File {
+                        size: entry.metadata().map(|m| m.len()).unwrap_or(0),
+                    }
+                };
+                if let Err(e) = remove_file_or_dir(entry.path(), file_or_dir, removal) {
                     eprintln!("Error removing '{}': {}", entry.path().display(), e);
                     success = false;
                 }
             }
             Err(e) => {
-                eprintln!("Error accessing file: {}", e);
-                success = false;
+                let is_permission_denied =
+                    e.io_error().map(|io_e| io_e.kind()) == Some(io::ErrorKind::PermissionDenied);
+
+                match e.path().filter(|_| is_permission_denied) {
+                    Some(dir_path) => match recover_directory_access(dir_path) {
+                        Ok(()) => {
+                            if !remove_walk(
+                                WalkDir::new(dir_path).contents_first(true).min_depth(1),
+                                removal,
+                            ) {
+                                success = false;
+                            }
+                        }
+                        Err(chmod_err) => {
+                            eprintln!(
+                                "Error accessing '{}': {} (failed to recover permissions: {})",
+                                dir_path.display(),
+                                e,
+                                chmod_err
+                            );
+                            success = false;
+                        }
+                    },
+                    None => {
+                        eprintln!("Error accessing file: {}", e);
+                        success = false;
+                    }
+                }
             }
         }
     }
 
-    if !success {
-        bail!("One or more errors deleting '{}'", path.display());
+    success
+}
+
+/// Grant the current user read, write and execute permission on `path`,
+/// preserving the rest of the mode.
+fn recover_directory_access(path: &Path) -> io::Result<()> {
+    let metadata = path.metadata()?;
+    let mut permissions = metadata.permissions();
+    set_readable_executable(&mut permissions);
+    fs::set_permissions(path, permissions)
+}
+
+/// Like `fs::read_dir`, but if the directory can't even be opened because it
+/// is missing its read or execute bit, grant them back (mirroring the
+/// recovery `remove_walk` does for the serial path) and retry once before
+/// giving up.
+fn read_dir_with_recovery(path: &Path) -> io::Result<fs::ReadDir> {
+    match fs::read_dir(path) {
+        Ok(read_dir) => Ok(read_dir),
+        Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {
+            recover_directory_access(path)?;
+            fs::read_dir(path)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Recursively remove `path`. Children of a directory are deleted
+/// concurrently via rayon's `par_iter`, and `path` itself is only removed
+/// once every child's task has joined. Errors are pushed onto `failures`
+/// rather than aborting the rest of the tree.
+fn remove_path_parallel(path: &Path, removal: Option<&Removal>, failures: &Mutex<Vec<String>>) {
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            failures
+                .lock()
+                .unwrap()
+                .push(format!("Error accessing '{}': {}", path.display(), e));
+            return;
+        }
+    };
+
+    // Check `is_symlink()` first: a symlink must never be descended into,
+    // even when it is a Windows directory symlink/junction.
+    if metadata.is_symlink() {
+        if let Err(e) = remove_file_or_dir(
+            path,
+            FileOrDir::Symlink {
+                size: metadata.len(),
+            },
+            removal,
+        ) {
+            failures
+                .lock()
+                .unwrap()
+                .push(format!("Error removing '{}': {}", path.display(), e));
+        }
+    } else if metadata.is_dir() {
+        let read_dir = match read_dir_with_recovery(path) {
+            Ok(read_dir) => read_dir,
+            Err(e) => {
+                failures
+                    .lock()
+                    .unwrap()
+                    .push(format!("Error reading directory '{}': {}", path.display(), e));
+                return;
+            }
+        };
+
+        let children = match read_dir
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<io::Result<Vec<PathBuf>>>()
+        {
+            Ok(children) => children,
+            Err(e) => {
+                failures
+                    .lock()
+                    .unwrap()
+                    .push(format!("Error reading directory '{}': {}", path.display(), e));
+                return;
+            }
+        };
+
+        children
+            .par_iter()
+            .for_each(|child| remove_path_parallel(child, removal, failures));
+
+        if let Err(e) = remove_file_or_dir(path, FileOrDir::Dir, removal) {
+            failures
+                .lock()
+                .unwrap()
+                .push(format!("Error removing '{}': {}", path.display(), e));
+        }
+    } else if let Err(e) = remove_file_or_dir(
+        path,
+        FileOrDir::File {
+            size: metadata.len(),
+        },
+        removal,
+    ) {
+        failures
+            .lock()
+            .unwrap()
+            .push(format!("Error removing '{}': {}", path.display(), e));
+    }
+}
+
+/// Record `file_or_dir` into `removal`'s `--stats` totals, unless `removed`
+/// is false (the still-running-executable leniency in `remove_with_retry`
+/// warns and moves on without actually deleting anything).
+fn record_if_removed(removed: bool, removal: Option<&Removal>, file_or_dir: &FileOrDir) {
+    if removed {
+        if let Some(removal) = removal {
+            removal.record(file_or_dir);
+        }
     }
-    Ok(())
 }
 
 /// Delete a single file or directory.
-fn remove_file_or_dir(path: &Path, file_or_dir: FileOrDir) -> Result<()> {
-    // The function to use for deletion.
-    let remove_item = match file_or_dir {
-        FileOrDir::File => fs::remove_file,
-        FileOrDir::Dir => fs::remove_dir,
+fn remove_file_or_dir(path: &Path, file_or_dir: FileOrDir, removal: Option<&Removal>) -> Result<()> {
+    // On Windows, rename the entry into its parent directory under a
+    // throwaway name right before deleting it, so that a pending delete
+    // left behind by an open handle doesn't make removing the parent
+    // directory fail. Fall back to deleting in place if the rename itself
+    // doesn't work.
+    #[cfg(windows)]
+    let renamed_path = rename_before_delete(path).ok();
+    #[cfg(windows)]
+    let path: &Path = renamed_path.as_deref().unwrap_or(path);
+
+    // The function to use for deletion. `fs::remove_file`/`fs::remove_dir`
+    // are generic fn items that can't coerce directly to the concrete
+    // `fn(&Path) -> io::Result<()>` this binding needs, so pin the lifetime
+    // with a closure; `remove_symlink` is already that concrete type.
+    let remove_item: fn(&Path) -> io::Result<()> = match file_or_dir {
+        FileOrDir::File { .. } => |p: &Path| fs::remove_file(p),
+        FileOrDir::Dir => |p: &Path| fs::remove_dir(p),
+        FileOrDir::Symlink { .. } => remove_symlink,
     };
 
     // Try to delete the file or directory. Return success/failure unless it
-    // failed with `PermissionDenied`.
+    // failed with `PermissionDenied`. (On Windows `original_error` ends up
+    // unused: the all-already-writable case below retries instead of
+    // surfacing it.)
+    #[cfg_attr(windows, allow(unused_variables))]
     let original_error = match remove_item(path) {
-        Ok(_) => return Ok(()),
+        Ok(_) => {
+            if let Some(removal) = removal {
+                removal.record(&file_or_dir);
+            }
+            return Ok(());
+        }
         Err(e) if e.kind() == io::ErrorKind::PermissionDenied => e,
+        // `ERROR_DIR_NOT_EMPTY` (raw OS error 145): the directory is
+        // otherwise writable, but one of its children is still being torn
+        // down by the OS (e.g. a delete-pending rename just below it).
+        // There's nothing to `chmod`; just retry with backoff.
+        #[cfg(windows)]
+        Err(e) if e.raw_os_error() == Some(145) => {
+            let removed = remove_with_retry(remove_item, path, &file_or_dir)?;
+            record_if_removed(removed, removal, &file_or_dir);
+            return Ok(());
+        }
         Err(e) => return Err(e.into()),
     };
 
@@ -93,9 +415,12 @@ fn remove_file_or_dir(path: &Path, file_or_dir: FileOrDir) -> Result<()> {
     //
     // Windows also has a proper ACL system but we don't try to use it.
 
-    let permission_target = path_to_make_writable(path, file_or_dir)?;
+    let permission_target = path_to_make_writable(path, &file_or_dir)?;
 
-    let metadata = match permission_target.metadata() {
+    // Use `symlink_metadata` rather than `metadata`: `permission_target` may
+    // be a symlink itself (on non-Unix it's `path` unchanged), and we must
+    // never stat, let alone `set_permissions`, its target.
+    let metadata = match permission_target.symlink_metadata() {
         Ok(m) => m,
         Err(e) => {
             bail!(
@@ -109,7 +434,17 @@ fn remove_file_or_dir(path: &Path, file_or_dir: FileOrDir) -> Result<()> {
 
     if is_writable(&permissions) {
         // The file/directory (or parent directory on Unix) were writable but
-        // we got permission denied anyway.
+        // we got permission denied anyway. On Windows this is the common
+        // case of the entry still being held open by another process (e.g.
+        // a just-exited executable whose handle hasn't closed yet), so
+        // retry with backoff instead of giving up immediately.
+        #[cfg(windows)]
+        {
+            let removed = remove_with_retry(remove_item, path, &file_or_dir)?;
+            record_if_removed(removed, removal, &file_or_dir);
+            return Ok(());
+        }
+        #[cfg(not(windows))]
         return Err(original_error.into());
     }
 
@@ -127,7 +462,119 @@ fn remove_file_or_dir(path: &Path, file_or_dir: FileOrDir) -> Result<()> {
         }
     }
     // Try deleting it one last time.
-    Ok(remove_item(path)?)
+    #[cfg(windows)]
+    let removed = remove_with_retry(remove_item, path, &file_or_dir)?;
+    #[cfg(not(windows))]
+    let removed = {
+        remove_item(path)?;
+        true
+    };
+
+    record_if_removed(removed, removal, &file_or_dir);
+    Ok(())
+}
+
+/// Remove a symlink without ever following it to its target.
+#[cfg(unix)]
+fn remove_symlink(path: &Path) -> io::Result<()> {
+    fs::remove_file(path)
+}
+
+/// Remove a symlink without ever following it to its target. On Windows a
+/// directory symlink/junction is itself reported as a directory (it carries
+/// `FILE_ATTRIBUTE_DIRECTORY` as well as `FILE_ATTRIBUTE_REPARSE_POINT`), so
+/// it has to go through `RemoveDirectory` rather than `DeleteFile`.
+#[cfg(not(unix))]
+fn remove_symlink(path: &Path) -> io::Result<()> {
+    // `is_dir()` is never true for a symlink, so ask for a directory
+    // symlink/junction explicitly.
+    #[cfg(windows)]
+    let is_dir = {
+        use os::windows::fs::FileTypeExt;
+        fs::symlink_metadata(path)?.file_type().is_symlink_dir()
+    };
+    #[cfg(not(windows))]
+    let is_dir = fs::symlink_metadata(path)?.is_dir();
+
+    if is_dir {
+        fs::remove_dir(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+/// Rename `path` into its parent directory under a throwaway, collision-free
+/// name, returning the new path. `DeleteFile`/`RemoveDirectory` only mark an
+/// entry for deletion; it lingers under its original name in
+/// `STATUS_DELETE_PENDING` until every open handle to it closes, which makes
+/// removing the now-supposedly-empty parent directory fail intermittently
+/// with "directory not empty". Renaming the entry out from under its
+/// original name means the parent no longer sees it by that name, so
+/// `fs::remove_dir` on the parent can succeed immediately even while the OS
+/// finishes the unlink in the background.
+#[cfg(windows)]
+fn rename_before_delete(path: &Path) -> io::Result<PathBuf> {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let parent = path
+        .parent()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "path has no parent directory"))?;
+
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let temp_path = parent.join(format!(".obliterate-tmp-{}", n));
+
+    fs::rename(path, &temp_path)?;
+    Ok(temp_path)
+}
+
+/// Retry `remove_item(path)` with exponential backoff on the transient
+/// errors Windows can return while a directory's children are still being
+/// flushed from the filesystem stack (`ERROR_DIR_NOT_EMPTY`, raw OS error
+/// 145) or while another process briefly holds an open handle
+/// (`PermissionDenied`). Gives up after a fixed number of attempts.
+///
+/// A `PermissionDenied` on a *file* that never clears usually means it is a
+/// still-running executable (matching how build-clean tooling handles its
+/// own binary): rather than failing the whole tree for that, we warn and
+/// report it as not removed so the caller doesn't count it in `--stats`.
+/// Directories and symlinks get no such pass, since that explanation doesn't
+/// apply to them.
+///
+/// Returns whether the entry was actually removed.
+#[cfg(windows)]
+fn remove_with_retry(
+    remove_item: fn(&Path) -> io::Result<()>,
+    path: &Path,
+    file_or_dir: &FileOrDir,
+) -> Result<bool> {
+    const MAX_ATTEMPTS: u32 = 10;
+    const MAX_DELAY: Duration = Duration::from_millis(256);
+
+    let mut delay = Duration::from_millis(1);
+
+    for attempt in 1..=MAX_ATTEMPTS {
+        let e = match remove_item(path) {
+            Ok(()) => return Ok(true),
+            Err(e) => e,
+        };
+
+        let is_transient = e.kind() == io::ErrorKind::PermissionDenied || e.raw_os_error() == Some(145);
+        if !is_transient || attempt == MAX_ATTEMPTS {
+            if e.kind() == io::ErrorKind::PermissionDenied && matches!(file_or_dir, FileOrDir::File { .. }) {
+                eprintln!(
+                    "Warning: permission denied removing '{}', it is probably still running; skipping",
+                    path.display()
+                );
+                return Ok(false);
+            }
+            return Err(e.into());
+        }
+
+        std::thread::sleep(delay);
+        delay = (delay * 2).min(MAX_DELAY);
+    }
+
+    unreachable!("loop always returns on its last iteration")
 }
 
 #[cfg(unix)]
@@ -159,14 +606,27 @@ fn is_writable(permissions: &fs::Permissions) -> bool {
 }
 
 #[cfg(unix)]
-fn path_to_make_writable(path: &Path, _file_or_dir: FileOrDir) -> Result<&Path> {
+fn set_readable_executable(permissions: &mut fs::Permissions) {
+    use os::unix::prelude::PermissionsExt;
+    // Grant read, write and execute to the user, leaving group/other bits
+    // untouched, so the directory can be opened and enumerated again.
+    permissions.set_mode(permissions.mode() | 0o700);
+}
+
+#[cfg(not(unix))]
+fn set_readable_executable(permissions: &mut fs::Permissions) {
+    permissions.set_readonly(false);
+}
+
+#[cfg(unix)]
+fn path_to_make_writable<'a>(path: &'a Path, _file_or_dir: &FileOrDir) -> Result<&'a Path> {
     path.parent().ok_or(anyhow!(
         "Cannot make parent path writable because it is in the root directory"
     ))
 }
 
 #[cfg(not(unix))]
-fn path_to_make_writable(path: &Path, file_or_dir: FileOrDir) -> Result<&Path> {
+fn path_to_make_writable<'a>(path: &'a Path, _file_or_dir: &FileOrDir) -> Result<&'a Path> {
     Ok(path)
 }
 
@@ -185,7 +645,22 @@ mod test {
         fs::write(path.join("dir1/file1"), "hello").unwrap();
         fs::write(path.join("dir1/dir2/file1"), "world").unwrap();
 
-        remove_path(&path.join("dir1")).unwrap();
+        remove_path(&path.join("dir1"), 1, None).unwrap();
+
+        assert!(!&path.join("dir1").exists());
+    }
+
+    #[test]
+    fn simple_parallel() {
+        let tmp_dir = TempDir::new("example").unwrap();
+        let path = tmp_dir.path();
+
+        fs::create_dir(path.join("dir1")).unwrap();
+        fs::create_dir(path.join("dir1/dir2")).unwrap();
+        fs::write(path.join("dir1/file1"), "hello").unwrap();
+        fs::write(path.join("dir1/dir2/file1"), "world").unwrap();
+
+        remove_path(&path.join("dir1"), 4, None).unwrap();
 
         assert!(!&path.join("dir1").exists());
     }
@@ -206,7 +681,7 @@ mod test {
         // TODO: set_permissions is weird; it changes the `all` permission not `user`.
         fs::set_permissions(file_path, permissions).unwrap();
 
-        remove_path(&path.join("dir1")).unwrap();
+        remove_path(&path.join("dir1"), 1, None).unwrap();
 
         assert!(!&path.join("dir1").exists());
     }
@@ -226,7 +701,53 @@ mod test {
         permissions.set_readonly(true);
         fs::set_permissions(file_path, permissions).unwrap();
 
-        remove_path(&path.join("dir1")).unwrap();
+        remove_path(&path.join("dir1"), 1, None).unwrap();
+
+        assert!(!&path.join("dir1").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unreadable_dir() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp_dir = TempDir::new("example").unwrap();
+        let path = tmp_dir.path();
+
+        fs::create_dir(path.join("dir1")).unwrap();
+        fs::create_dir(path.join("dir1/dir2")).unwrap();
+        fs::write(path.join("dir1/file1"), "hello").unwrap();
+        fs::write(path.join("dir1/dir2/file1"), "world").unwrap();
+
+        // Remove every permission bit from `dir2`, read and execute
+        // included. `WalkDir` can't even enumerate its contents without
+        // those bits back.
+        fs::set_permissions(path.join("dir1/dir2"), fs::Permissions::from_mode(0o000)).unwrap();
+
+        remove_path(&path.join("dir1"), 1, None).unwrap();
+
+        assert!(!&path.join("dir1").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unreadable_dir_parallel() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp_dir = TempDir::new("example").unwrap();
+        let path = tmp_dir.path();
+
+        fs::create_dir(path.join("dir1")).unwrap();
+        fs::create_dir(path.join("dir1/dir2")).unwrap();
+        fs::write(path.join("dir1/file1"), "hello").unwrap();
+        fs::write(path.join("dir1/dir2/file1"), "world").unwrap();
+
+        // Same as `unreadable_dir`, but with multiple jobs so the recovery
+        // in `read_dir_with_recovery` (used by `remove_path_parallel`) is
+        // exercised too, not just the serial `remove_walk` one.
+        fs::set_permissions(path.join("dir1/dir2"), fs::Permissions::from_mode(0o000)).unwrap();
+
+        remove_path(&path.join("dir1"), 4, None).unwrap();
 
         assert!(!&path.join("dir1").exists());
     }
@@ -248,8 +769,128 @@ mod test {
             fs::set_permissions(file_path, permissions).unwrap();
         }
 
-        remove_path(&path.join("dir1")).unwrap();
+        remove_path(&path.join("dir1"), 1, None).unwrap();
 
         assert!(!&path.join("dir1").exists());
     }
+
+    #[test]
+    fn stats() {
+        let tmp_dir = TempDir::new("example").unwrap();
+        let path = tmp_dir.path();
+
+        fs::create_dir(path.join("dir1")).unwrap();
+        fs::create_dir(path.join("dir1/dir2")).unwrap();
+        fs::write(path.join("dir1/file1"), "hello").unwrap();
+        fs::write(path.join("dir1/dir2/file1"), "worldwide").unwrap();
+
+        let removal = Removal::default();
+        remove_path(&path.join("dir1"), 1, Some(&removal)).unwrap();
+
+        assert_eq!(removal.num_files.load(Ordering::Relaxed), 2);
+        assert_eq!(removal.num_dirs.load(Ordering::Relaxed), 2);
+        assert_eq!(removal.total_bytes.load(Ordering::Relaxed), 5 + 9);
+    }
+
+    #[test]
+    fn format_bytes_units() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(5_046_586_573), "4.7 GiB");
+    }
+
+    #[test]
+    fn format_count_separators() {
+        assert_eq!(format_count(0), "0");
+        assert_eq!(format_count(921), "921");
+        assert_eq!(format_count(12_384), "12,384");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlink_to_outside_tree() {
+        use std::os::unix::fs::{symlink, PermissionsExt};
+
+        let tmp_dir = TempDir::new("example").unwrap();
+        let path = tmp_dir.path();
+
+        fs::create_dir(path.join("dir1")).unwrap();
+        fs::write(path.join("target"), "hello").unwrap();
+        symlink(path.join("target"), path.join("dir1/link")).unwrap();
+
+        // Make the target read-only; deleting the symlink must never touch
+        // it.
+        let mut permissions = path.join("target").metadata().unwrap().permissions();
+        permissions.set_mode(permissions.mode() & !0o200);
+        fs::set_permissions(path.join("target"), permissions).unwrap();
+
+        remove_path(&path.join("dir1"), 1, None).unwrap();
+
+        assert!(!&path.join("dir1").exists());
+        assert!(path.join("target").exists());
+        assert_eq!(
+            path.join("target").metadata().unwrap().permissions().mode() & 0o777,
+            0o444,
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlink_to_outside_tree_parallel() {
+        use std::os::unix::fs::{symlink, PermissionsExt};
+
+        let tmp_dir = TempDir::new("example").unwrap();
+        let path = tmp_dir.path();
+
+        fs::create_dir(path.join("dir1")).unwrap();
+        fs::write(path.join("target"), "hello").unwrap();
+        symlink(path.join("target"), path.join("dir1/link")).unwrap();
+
+        let mut permissions = path.join("target").metadata().unwrap().permissions();
+        permissions.set_mode(permissions.mode() & !0o200);
+        fs::set_permissions(path.join("target"), permissions).unwrap();
+
+        remove_path(&path.join("dir1"), 4, None).unwrap();
+
+        assert!(!&path.join("dir1").exists());
+        assert!(path.join("target").exists());
+        assert_eq!(
+            path.join("target").metadata().unwrap().permissions().mode() & 0o777,
+            0o444,
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn rename_before_delete_renames_into_parent() {
+        let tmp_dir = TempDir::new("example").unwrap();
+        let path = tmp_dir.path();
+
+        fs::write(path.join("file1"), "hello").unwrap();
+
+        let renamed = rename_before_delete(&path.join("file1")).unwrap();
+
+        assert_eq!(renamed.parent().unwrap(), path);
+        assert!(!path.join("file1").exists());
+        assert!(renamed.exists());
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn remove_with_retry_succeeds_immediately() {
+        let tmp_dir = TempDir::new("example").unwrap();
+        let path = tmp_dir.path();
+
+        fs::write(path.join("file1"), "hello").unwrap();
+
+        let removed = remove_with_retry(
+            |p: &Path| fs::remove_file(p),
+            &path.join("file1"),
+            &FileOrDir::File { size: 5 },
+        )
+        .unwrap();
+
+        assert!(removed);
+        assert!(!path.join("file1").exists());
+    }
 }